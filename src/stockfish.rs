@@ -1,10 +1,19 @@
-use std::{io, num::NonZeroU8, path::PathBuf, process::Stdio, time::Duration, os::unix::process::CommandExt};
+use std::{
+    collections::VecDeque,
+    io,
+    num::{NonZeroU8, NonZeroUsize},
+    os::unix::process::CommandExt,
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::{Duration, Instant},
+};
 
 use shakmaty::variant::Variant;
 use tokio::{
     io::{AsyncBufReadExt as _, AsyncWriteExt as _, BufReader, BufWriter, Lines},
     process::{ChildStdin, ChildStdout, Command},
     sync::{mpsc, oneshot},
+    time::interval,
 };
 
 use crate::{
@@ -26,12 +35,15 @@ pub fn channel(
         StockfishActor {
             rx,
             exe,
-            init: Some(init),
+            init,
+            initialized: false,
+            go_written: false,
             logger,
         },
     )
 }
 
+#[derive(Clone)]
 pub struct StockfishStub {
     tx: mpsc::Sender<StockfishMessage>,
 }
@@ -46,12 +58,61 @@ impl StockfishStub {
             .map_err(|_| PositionFailed { batch_id })?;
         response.await.map_err(|_| PositionFailed { batch_id })
     }
+
+    // Whether the actor backing this stub has exited for good (e.g. it gave
+    // up after a crash loop). A single failed `go` does not imply this: the
+    // actor may have just respawned the engine process internally and is
+    // still alive and serving `tx` for the next job.
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+
+    // Like `go`, but rather than waiting for a single final response, returns
+    // a receiver that is fed a snapshot of the current analysis (depth,
+    // scores, pvs, ...) on every completed depth iteration, plus a final
+    // snapshot once `bestmove` arrives. The receiver closes once the search
+    // is done. Slow consumers block the engine only briefly: per-depth
+    // snapshots are best-effort and dropped if the channel is still full of
+    // an earlier update, and the final snapshot waits at most
+    // `STREAMING_FINAL_SEND_GRACE` for space before it is dropped too.
+    pub async fn go_streaming(
+        &mut self,
+        position: Position,
+    ) -> Result<mpsc::Receiver<PositionResponse>, PositionFailed> {
+        let (updates, receiver) = mpsc::channel(STREAMING_UPDATES_BUFFER);
+        let batch_id = position.work.id();
+        self.tx
+            .send(StockfishMessage::GoStreaming { position, updates })
+            .await
+            .map_err(|_| PositionFailed { batch_id })?;
+        Ok(receiver)
+    }
 }
 
+// Small buffer for streaming analysis updates: each snapshot supersedes the
+// previous one, so there is no point buffering many of them up. When the
+// buffer is full, the newest per-depth snapshot is the one dropped.
+const STREAMING_UPDATES_BUFFER: usize = 4;
+
+// How long the final bestmove snapshot waits for buffer space in a lagging
+// consumer before giving up. Unlike the droppable per-depth snapshots, the
+// completed result should reach a live consumer, so this is a blocking send
+// rather than `try_send` -- but still bounded, so a consumer that never
+// drains its end can't stall the engine indefinitely.
+const STREAMING_FINAL_SEND_GRACE: Duration = Duration::from_secs(1);
+
 pub struct StockfishActor {
     rx: mpsc::Receiver<StockfishMessage>,
     exe: PathBuf,
-    init: Option<StockfishInit>,
+    // Retained (not consumed) so it can be replayed against a respawned
+    // process, unlike the per-process `initialized` flag below.
+    init: StockfishInit,
+    initialized: bool,
+    // Whether the `go` command of the search currently in flight has
+    // actually been flushed to the engine. Reset at the start of every
+    // `go()` call, and read by `cancel` if that same call is abandoned
+    // mid-flight.
+    go_written: bool,
     logger: Logger,
 }
 
@@ -61,9 +122,13 @@ enum StockfishMessage {
         position: Position,
         callback: oneshot::Sender<PositionResponse>,
     },
+    GoStreaming {
+        position: Position,
+        updates: mpsc::Sender<PositionResponse>,
+    },
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct StockfishInit {
     pub nnue: String,
 }
@@ -91,7 +156,8 @@ impl Stdout {
 #[derive(Debug)]
 enum EngineError {
     IoError(io::Error),
-    Shutdown,
+    Timeout,
+    ProcessExited,
 }
 
 impl From<io::Error> for EngineError {
@@ -113,15 +179,121 @@ fn new_process_group(command: &mut Command) -> &mut Command {
     command.creation_flags(create_new_process_group)
 }
 
+// Slack added on top of the time/nodes budget implied by the work, to
+// account for engine startup and bookkeeping overhead around the actual
+// search.
+const WATCHDOG_SLACK: Duration = Duration::from_secs(15);
+
+// How long we wait for `bestmove` after sending `stop` to a wedged engine,
+// before giving up and killing the process group outright.
+const WATCHDOG_STOP_GRACE: Duration = Duration::from_secs(5);
+
+// The deadline after which we consider the engine wedged, derived from the
+// parameters of the work being done plus a slack margin. This is not a
+// precise bound (nodes-based searches do not have a well defined wall time),
+// just generous enough that a live engine will never trip it.
+//
+// Crucially, this bounds the gap between `info`/`bestmove` lines in the
+// response loop below (it resets on each `info` line; other output does not
+// reset it), not the search as a whole -- so it's fine for it to
+// be loose even for a long-running analysis, as long as it is tight enough
+// to catch an engine that has gone silent entirely.
+fn watchdog_deadline(work: &Work) -> Duration {
+    match work {
+        Work::Move { level, clock, .. } => {
+            let mut deadline = level.time() + WATCHDOG_SLACK;
+            if let Some(clock) = clock {
+                deadline += Duration::from(clock.wtime).max(Duration::from(clock.btime));
+            }
+            deadline
+        }
+        // No well-defined wall-clock budget to derive from `nodes`/`depth`
+        // (search speed depends on position and hardware): a flat bound,
+        // generous enough for the slowest expected per-line gap, reset on
+        // every `info` line per the note above.
+        Work::Analysis { .. } => Duration::from_secs(90) + WATCHDOG_SLACK,
+    }
+}
+
+#[cfg(unix)]
+fn kill_process_group(pid: u32) {
+    // pid == pgid, because new_process_group() calls process_group(0) on
+    // unix, so this also reaps any helper threads/processes Stockfish may
+    // have spawned.
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(windows)]
+fn kill_process_group(_pid: u32) {
+    // No good equivalent of killing a process group on Windows. kill_on_drop
+    // on the Child will terminate the immediate process once the actor
+    // drops it.
+}
+
+// Exponential backoff between respawn attempts (1s, 2s, 4s, ... capped).
+const RESPAWN_BACKOFF_BASE: Duration = Duration::from_secs(1);
+const RESPAWN_BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+// If the engine crashes more than this many times within the sliding
+// window, it is considered a crash loop and the actor gives up for good,
+// rather than spinning and respawning forever.
+const RESPAWN_WINDOW: Duration = Duration::from_secs(5 * 60);
+const RESPAWN_MAX_IN_WINDOW: usize = 10;
+
 impl StockfishActor {
-    pub async fn run(self) {
-        let logger = self.logger.clone();
-        if let Err(EngineError::IoError(err)) = self.run_inner().await {
-            logger.error(&format!("Engine error: {}", err));
+    // Supervises a single process over its whole lifetime: spawns it, serves
+    // `rx` against it, and if it crashes (or a fatal I/O error/watchdog
+    // timeout kills it), respawns it and resumes, rather than letting the
+    // actor die with the process. Respawns are gated by exponential backoff
+    // and a max-restart count within a sliding window, so a persistently
+    // broken engine (e.g. a bad `exe` path) does not spin-loop forever.
+    pub async fn run(mut self) {
+        let mut backoff = RESPAWN_BACKOFF_BASE;
+        let mut restarts: VecDeque<Instant> = VecDeque::new();
+
+        loop {
+            match self.run_inner().await {
+                Ok(()) => return,
+                Err(err) => {
+                    let now = Instant::now();
+                    restarts.push_back(now);
+                    while let Some(&oldest) = restarts.front() {
+                        if now.duration_since(oldest) > RESPAWN_WINDOW {
+                            restarts.pop_front();
+                        } else {
+                            break;
+                        }
+                    }
+                    if restarts.len() > RESPAWN_MAX_IN_WINDOW {
+                        self.logger.error(&format!(
+                            "Engine crashed {} times in the last {:?} ({:?}), giving up",
+                            restarts.len(),
+                            RESPAWN_WINDOW,
+                            err
+                        ));
+                        return;
+                    }
+
+                    self.logger.error(&format!(
+                        "Engine crashed ({:?}), respawning in {:?}",
+                        err, backoff
+                    ));
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(RESPAWN_BACKOFF_MAX);
+                }
+            }
         }
     }
 
-    async fn run_inner(mut self) -> Result<(), EngineError> {
+    // Serves `rx` against a single freshly spawned process, until either the
+    // channel closes for good (`Ok(())`, the stub side has gone away and no
+    // more work will ever arrive) or the process/engine needs to be
+    // respawned (`Err`).
+    async fn run_inner(&mut self) -> Result<(), EngineError> {
+        self.initialized = false;
+
         let mut child = new_process_group(
             Command::new(&self.exe)
                 .stdout(Stdio::piped())
@@ -148,9 +320,9 @@ impl StockfishActor {
             tokio::select! {
                 msg = self.rx.recv() => {
                     if let Some(msg) = msg {
-                        self.handle_message(&mut stdout, &mut stdin, msg).await?;
+                        self.handle_message(&mut stdout, &mut stdin, pid, msg).await?;
                     } else {
-                        break;
+                        return Ok(());
                     }
                 }
                 status = child.wait() => {
@@ -162,18 +334,17 @@ impl StockfishActor {
                             self.logger.error(&format!("Stockfish process {} exited with status {}", pid, status));
                         }
                     }
-                    break;
+                    return Err(EngineError::ProcessExited);
                 }
             }
         }
-
-        Ok(())
     }
 
     async fn handle_message(
         &mut self,
         stdout: &mut Stdout,
         stdin: &mut BufWriter<ChildStdin>,
+        pid: u32,
         msg: StockfishMessage,
     ) -> Result<(), EngineError> {
         match msg {
@@ -182,13 +353,82 @@ impl StockfishActor {
                 position,
             } => {
                 tokio::select! {
-                    _ = callback.closed() => Err(EngineError::Shutdown),
-                    res = self.go(stdout, stdin, position) => {
+                    _ = callback.closed() => self.cancel(stdin, stdout, pid).await,
+                    res = self.go(stdout, stdin, pid, position, None) => {
                         callback.send(res?).nevermind("go receiver dropped");
                         Ok(())
                     }
                 }
             }
+            StockfishMessage::GoStreaming { position, updates } => {
+                let closed = updates.clone();
+                tokio::select! {
+                    _ = closed.closed() => self.cancel(stdin, stdout, pid).await,
+                    res = self.go(stdout, stdin, pid, position, Some(updates)) => {
+                        res?;
+                        Ok(())
+                    }
+                }
+            }
+        }
+    }
+
+    // The caller went away (cancelled or abandoned the job) while a search
+    // was in flight. Rather than tearing down the whole process (which would
+    // force a respawn, re-init and NNUE reload on the next job), cooperate
+    // with the engine: ask it to stop and resynchronize to the `bestmove`
+    // boundary, discarding the result, so the process can be reused as-is.
+    //
+    // Cancellation can land at any await point of the abandoned `go` future,
+    // including before the `go` command was ever written (e.g. still in
+    // `init()`/setup). In that case the engine was never told to search, so
+    // there is nothing to stop and no `bestmove` to wait for: `go_written`
+    // lets us skip straight to reusing the untouched, perfectly healthy
+    // process instead of waiting out the grace period and killing it.
+    //
+    // Once `go` has been written, `stop` is bounded by the same watchdog
+    // grace period as `watchdog_expired`, with the same hard-kill fallback,
+    // rather than risking an unbounded read here.
+    async fn cancel(
+        &mut self,
+        stdin: &mut BufWriter<ChildStdin>,
+        stdout: &mut Stdout,
+        pid: u32,
+    ) -> Result<(), EngineError> {
+        if !self.go_written {
+            self.logger
+                .debug("Search cancelled before go was sent, nothing to stop");
+            return Ok(());
+        }
+
+        self.logger.debug("Search cancelled, sending stop");
+        stdin.write_all(b"stop\n").await?;
+        stdin.flush().await?;
+
+        match tokio::time::timeout(WATCHDOG_STOP_GRACE, self.drain_until_bestmove(stdout)).await {
+            Ok(result) => {
+                result?;
+                Ok(())
+            }
+            Err(_) => {
+                self.logger.error(&format!(
+                    "Engine process {} unresponsive to stop after cancel, killing process group",
+                    pid
+                ));
+                kill_process_group(pid);
+                Err(EngineError::Timeout)
+            }
+        }
+    }
+
+    // Reads and discards lines until (and including) a `bestmove` line,
+    // resynchronizing with the engine after a `stop`.
+    async fn drain_until_bestmove(&self, stdout: &mut Stdout) -> io::Result<()> {
+        loop {
+            let line = stdout.read_line().await?;
+            if line.starts_with("bestmove") {
+                return Ok(());
+            }
         }
     }
 
@@ -197,9 +437,9 @@ impl StockfishActor {
         stdout: &mut Stdout,
         stdin: &mut BufWriter<ChildStdin>,
     ) -> io::Result<()> {
-        if let Some(init) = self.init.take() {
+        if !self.initialized {
             stdin
-                .write_all(format!("setoption name EvalFile value {}\n", init.nnue).as_bytes())
+                .write_all(format!("setoption name EvalFile value {}\n", self.init.nnue).as_bytes())
                 .await?;
             stdin
                 .write_all(b"setoption name UCI_Chess960 value true\n")
@@ -220,16 +460,28 @@ impl StockfishActor {
                     ));
                 }
             }
+
+            self.initialized = true;
         }
         Ok(())
     }
 
+    // Runs a search to completion, returning the final response. If
+    // `updates` is given, a snapshot of the in-progress analysis is also
+    // pushed through it on every completed depth iteration, and a final
+    // snapshot once `bestmove` arrives, so that callers can show live
+    // analysis progress instead of waiting for the whole search.
     async fn go(
         &mut self,
         stdout: &mut Stdout,
         stdin: &mut BufWriter<ChildStdin>,
+        pid: u32,
         position: Position,
-    ) -> io::Result<PositionResponse> {
+        mut updates: Option<mpsc::Sender<PositionResponse>>,
+    ) -> Result<PositionResponse, EngineError> {
+        self.go_written = false;
+        let deadline = watchdog_deadline(&position.work);
+
         // Set global options (once).
         self.init(stdout, stdin).await?;
 
@@ -331,30 +583,70 @@ impl StockfishActor {
         stdin.write_all(go.join(" ").as_bytes()).await?;
         stdin.write_all(b"\n").await?;
         stdin.flush().await?;
+        self.go_written = true;
 
         // Process response.
         let mut scores = Matrix::new();
         let mut pvs = Matrix::new();
         let mut depth = 0;
+        let mut last_emitted_depth = 0;
         let mut multipv = NonZeroU8::new(1).unwrap();
         let mut time = Duration::default();
         let mut nodes = 0;
         let mut nps = None;
 
+        let mut last_progress = Instant::now();
         loop {
-            let line = stdout.read_line().await?;
+            let remaining = deadline.saturating_sub(last_progress.elapsed());
+            let line = match tokio::time::timeout(remaining, stdout.read_line()).await {
+                Ok(line) => line?,
+                Err(_) => return Err(self.watchdog_expired(stdin, stdout, pid).await),
+            };
             let mut parts = line.split(' ');
             match parts.next() {
                 Some("bestmove") => {
                     if scores.best().is_none() {
-                        return Err(io::Error::new(io::ErrorKind::InvalidData, "missing score"));
+                        return Err(
+                            io::Error::new(io::ErrorKind::InvalidData, "missing score").into()
+                        );
+                    }
+
+                    let best_move = parts.next().and_then(|m| m.parse().ok());
+
+                    if let Some(updates) = &mut updates {
+                        // Unlike the droppable per-depth snapshots below,
+                        // this is the completed result: give a lagging
+                        // consumer a short, bounded window to drain its
+                        // buffer instead of silently dropping it.
+                        match tokio::time::timeout(
+                            STREAMING_FINAL_SEND_GRACE,
+                            updates.send(PositionResponse {
+                                work: position.work.clone(),
+                                position_id: position.position_id.clone(),
+                                url: position.url.clone(),
+                                best_move,
+                                scores: scores.clone(),
+                                depth,
+                                pvs: pvs.clone(),
+                                time,
+                                nodes,
+                                nps,
+                            }),
+                        )
+                        .await
+                        {
+                            Ok(result) => result.nevermind("updates receiver dropped"),
+                            Err(_) => self.logger.warn(
+                                "Final analysis snapshot dropped: consumer did not drain in time",
+                            ),
+                        }
                     }
 
                     return Ok(PositionResponse {
                         work: position.work,
                         position_id: position.position_id,
                         url: position.url,
-                        best_move: parts.next().and_then(|m| m.parse().ok()),
+                        best_move,
                         scores,
                         depth,
                         pvs,
@@ -364,6 +656,7 @@ impl StockfishActor {
                     });
                 }
                 Some("info") => {
+                    last_progress = Instant::now();
                     while let Some(part) = parts.next() {
                         match part {
                             "multipv" => {
@@ -416,7 +709,8 @@ impl StockfishActor {
                                             return Err(io::Error::new(
                                                 io::ErrorKind::InvalidData,
                                                 "expected cp or mate",
-                                            ))
+                                            )
+                                            .into())
                                         }
                                     }
                                     .ok_or_else(|| {
@@ -436,6 +730,26 @@ impl StockfishActor {
                             _ => (),
                         }
                     }
+
+                    if let Some(updates) = &mut updates {
+                        if depth > last_emitted_depth && scores.best().is_some() {
+                            last_emitted_depth = depth;
+                            updates
+                                .try_send(PositionResponse {
+                                    work: position.work.clone(),
+                                    position_id: position.position_id.clone(),
+                                    url: position.url.clone(),
+                                    best_move: None,
+                                    scores: scores.clone(),
+                                    depth,
+                                    pvs: pvs.clone(),
+                                    time,
+                                    nodes,
+                                    nps,
+                                })
+                                .nevermind("updates receiver lagging or dropped");
+                        }
+                    }
                 }
                 _ => self
                     .logger
@@ -443,4 +757,388 @@ impl StockfishActor {
             }
         }
     }
+
+    // The engine has not produced an `info`/`bestmove` line within its
+    // deadline. Try `stop` first, so that a merely stalled search ends with
+    // a `bestmove`, and fall back to killing the whole process group if the
+    // engine does not even respond to that. Either way the search is
+    // abandoned and `EngineError::Timeout` is returned, after which `run`
+    // respawns the process.
+    async fn watchdog_expired(
+        &mut self,
+        stdin: &mut BufWriter<ChildStdin>,
+        stdout: &mut Stdout,
+        pid: u32,
+    ) -> EngineError {
+        self.logger.error(&format!(
+            "Engine process {} watchdog expired, sending stop",
+            pid
+        ));
+
+        let stopped = stdin.write_all(b"stop\n").await.and(stdin.flush().await);
+        if stopped.is_ok() {
+            let drained =
+                tokio::time::timeout(WATCHDOG_STOP_GRACE, self.drain_until_bestmove(stdout)).await;
+
+            if drained.is_ok() {
+                self.logger.warn(&format!(
+                    "Engine process {} resynchronized after stop, abandoning stalled search",
+                    pid
+                ));
+                return EngineError::Timeout;
+            }
+        }
+
+        self.logger.error(&format!(
+            "Engine process {} unresponsive, killing process group",
+            pid
+        ));
+        kill_process_group(pid);
+        EngineError::Timeout
+    }
+}
+
+// Dispatch queue is serviced on a fixed tick rather than per-message, so
+// that a burst of incoming positions coalesces into a single pass over the
+// engines instead of reshuffling work on every single message.
+const POOL_DISPATCH_TICK: Duration = Duration::from_millis(5);
+
+// Generous enough that a pool under heavy load queues rather than rejects
+// work outright.
+const POOL_QUEUE_BUFFER: usize = 256;
+
+// How many engines a position may be dispatched to (the first attempt plus
+// retries) before giving up on it. Bounds retries so a position that
+// reliably defeats every engine it touches (e.g. malformed input) doesn't
+// retry forever instead of eventually surfacing as `PositionFailed`.
+const POOL_MAX_ATTEMPTS: u32 = 3;
+
+/// Returns the number of engines a [`StockfishPool`] should spawn by
+/// default, i.e. one per available core.
+pub fn default_pool_size() -> NonZeroUsize {
+    std::thread::available_parallelism().unwrap_or_else(|_| NonZeroUsize::new(1).unwrap())
+}
+
+/// Multiplexes many positions over a bounded set of Stockfish processes,
+/// load-balancing each incoming [`Position`] to an idle engine and queueing
+/// the rest. A crashed or wedged engine is retired and respawned in place,
+/// without affecting jobs running on the other engines.
+pub struct StockfishPool {
+    tx: mpsc::Sender<PoolMessage>,
+}
+
+impl StockfishPool {
+    pub fn spawn(
+        exe: PathBuf,
+        init: StockfishInit,
+        logger: Logger,
+        size: NonZeroUsize,
+    ) -> StockfishPool {
+        let (tx, rx) = mpsc::channel(POOL_QUEUE_BUFFER);
+        let (done_tx, done_rx) = mpsc::channel(POOL_QUEUE_BUFFER);
+
+        let mut engines = Vec::with_capacity(size.get());
+        for _ in 0..size.get() {
+            engines.push(EngineSlot::spawn(&exe, &init, &logger));
+        }
+
+        let supervisor = PoolSupervisor {
+            exe,
+            init,
+            logger,
+            rx,
+            done_tx,
+            done_rx,
+            engines,
+            pending: VecDeque::new(),
+        };
+        tokio::spawn(supervisor.run());
+
+        StockfishPool { tx }
+    }
+
+    pub async fn go(&self, position: Position) -> Result<PositionResponse, PositionFailed> {
+        let (callback, response) = oneshot::channel();
+        let batch_id = position.work.id();
+        self.tx
+            .send(PoolMessage::Go { position, callback })
+            .await
+            .map_err(|_| PositionFailed { batch_id })?;
+        response.await.map_err(|_| PositionFailed { batch_id })
+    }
+}
+
+enum PoolMessage {
+    Go {
+        position: Position,
+        callback: oneshot::Sender<PositionResponse>,
+    },
+}
+
+struct QueuedJob {
+    position: Position,
+    callback: oneshot::Sender<PositionResponse>,
+    // How many engines this position has already been dispatched to before
+    // the attempt that is about to run (0 for a fresh job).
+    attempt: u32,
+}
+
+struct EngineDone {
+    index: usize,
+    crashed: bool,
+    // Set when `go` failed but the position has attempts left: re-enqueued
+    // by the supervisor for another engine, rather than surfaced as
+    // `PositionFailed` over a single transient hiccup.
+    retry: Option<QueuedJob>,
+}
+
+struct EngineSlot {
+    stub: StockfishStub,
+    busy: bool,
+}
+
+impl EngineSlot {
+    fn spawn(exe: &Path, init: &StockfishInit, logger: &Logger) -> EngineSlot {
+        let (stub, actor) = channel(exe.to_path_buf(), init.clone(), logger.clone());
+        tokio::spawn(actor.run());
+        EngineSlot { stub, busy: false }
+    }
+}
+
+struct PoolSupervisor {
+    exe: PathBuf,
+    init: StockfishInit,
+    logger: Logger,
+    rx: mpsc::Receiver<PoolMessage>,
+    done_tx: mpsc::Sender<EngineDone>,
+    done_rx: mpsc::Receiver<EngineDone>,
+    engines: Vec<EngineSlot>,
+    pending: VecDeque<QueuedJob>,
+}
+
+impl PoolSupervisor {
+    async fn run(mut self) {
+        let mut tick = interval(POOL_DISPATCH_TICK);
+        loop {
+            tokio::select! {
+                msg = self.rx.recv() => {
+                    match msg {
+                        Some(PoolMessage::Go { position, callback }) => {
+                            self.pending.push_back(QueuedJob { position, callback, attempt: 0 });
+                        }
+                        None => break,
+                    }
+                }
+                Some(done) = self.done_rx.recv() => {
+                    self.engines[done.index].busy = false;
+                    if done.crashed {
+                        self.logger.error(&format!("Engine {} crashed, respawning", done.index));
+                        self.engines[done.index] =
+                            EngineSlot::spawn(&self.exe, &self.init, &self.logger);
+                    }
+                    if let Some(job) = done.retry {
+                        self.pending.push_back(job);
+                    }
+                }
+                _ = tick.tick() => {
+                    self.dispatch_pending();
+                }
+            }
+        }
+    }
+
+    // Hands queued jobs to idle engines, up to one per tick per engine. This
+    // caps the number of concurrently active searches at the pool size,
+    // avoiding CPU oversubscription.
+    fn dispatch_pending(&mut self) {
+        for index in 0..self.engines.len() {
+            if self.pending.is_empty() {
+                break;
+            }
+            if !self.engines[index].busy {
+                let job = self.pending.pop_front().expect("pending is not empty");
+                self.dispatch(index, job);
+            }
+        }
+    }
+
+    fn dispatch(&mut self, index: usize, job: QueuedJob) {
+        self.engines[index].busy = true;
+        let mut stub = self.engines[index].stub.clone();
+        let done_tx = self.done_tx.clone();
+        let retry_position = job.position.clone();
+        let attempt = job.attempt;
+        tokio::spawn(async move {
+            let outcome = stub.go(job.position).await;
+            // A failed `go` alone doesn't mean the engine is gone: the actor
+            // supervises its own process and transparently respawns after a
+            // crash or a single stalled search's watchdog timeout. Only
+            // retire the slot once the stub's channel shows the actor has
+            // actually exited for good, so a transient timeout doesn't pay
+            // for a second, redundant respawn on top of the actor's own.
+            let crashed = outcome.is_err() && stub.is_closed();
+
+            // A single engine's hiccup shouldn't fail a position outright
+            // while the rest of the pool is healthy: re-enqueue it for
+            // another attempt, up to a bound, and only give up (by dropping
+            // `callback`, which surfaces as `PositionFailed` to the caller)
+            // once no engine has been able to serve it.
+            let retry = match outcome {
+                Ok(response) => {
+                    job.callback.send(response).nevermind("pool caller dropped");
+                    None
+                }
+                Err(_) if attempt + 1 < POOL_MAX_ATTEMPTS => Some(QueuedJob {
+                    position: retry_position,
+                    callback: job.callback,
+                    attempt: attempt + 1,
+                }),
+                Err(_) => None,
+            };
+
+            done_tx
+                .send(EngineDone {
+                    index,
+                    crashed,
+                    retry,
+                })
+                .await
+                .nevermind("pool supervisor gone");
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    use super::*;
+
+    // A tiny fake "stockfish" used to exercise the actor's process handling
+    // without a real engine binary. It understands just enough UCI to drive
+    // the scenario below: `isready` replies `readyok`, and a `go` command
+    // blocks (simulating an in-progress search) until a `stop` arrives, at
+    // which point it replies with `bestmove`.
+    //
+    // `Position`/`Work` (and thus the full `StockfishStub::go` surface) live
+    // in `api`/`ipc`, which are outside this module, so this drives the
+    // actor's process/IO handling directly rather than through a message.
+    fn write_fake_engine() -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "fishnet-fake-stockfish-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::write(
+            &path,
+            "#!/bin/sh\nwhile IFS= read -r line; do\n  case \"$line\" in\n    isready) echo readyok ;;\n    go\\ *)\n      while IFS= read -r inner; do\n        case \"$inner\" in\n          stop) echo \"bestmove 0000\"; break ;;\n        esac\n      done\n      ;;\n  esac\ndone\n",
+        )
+        .expect("write fake engine script");
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+            .expect("make fake engine executable");
+        path
+    }
+
+    // Regression test for the invariant `handle_message` relies on: after a
+    // search is cancelled mid-flight, the engine must be resynchronized to a
+    // `bestmove` boundary (and the process kept alive) so that the very same
+    // process can serve the next command, instead of paying for a respawn.
+    #[tokio::test]
+    async fn cancel_resyncs_engine_for_reuse() {
+        let exe = write_fake_engine();
+
+        let mut child = new_process_group(
+            Command::new(&exe)
+                .stdout(Stdio::piped())
+                .stdin(Stdio::piped())
+                .kill_on_drop(true),
+        )
+        .spawn()
+        .expect("spawn fake engine");
+        let pid = child.id().expect("pid");
+        let mut stdout = Stdout::new(child.stdout.take().expect("stdout"));
+        let mut stdin = BufWriter::new(child.stdin.take().expect("stdin"));
+
+        let (_tx, rx) = mpsc::channel(1);
+        let mut actor = StockfishActor {
+            rx,
+            exe,
+            init: StockfishInit {
+                nnue: String::new(),
+            },
+            initialized: true,
+            go_written: true,
+            logger: Logger::new(),
+        };
+
+        // Simulate a search in flight, then cancel it mid-search.
+        stdin.write_all(b"go nodes 1000000\n").await.unwrap();
+        stdin.flush().await.unwrap();
+        actor
+            .cancel(&mut stdin, &mut stdout, pid)
+            .await
+            .expect("cancel resyncs to bestmove");
+
+        // The process is still alive and resynchronized: a follow-up
+        // exchange succeeds on the very same process.
+        stdin.write_all(b"isready\n").await.unwrap();
+        stdin.flush().await.unwrap();
+        let line = stdout.read_line().await.unwrap();
+        assert_eq!(line.trim_end(), "readyok");
+
+        let _ = std::fs::remove_file(&actor.exe);
+    }
+
+    // Regression test for the case where cancellation lands before `go` was
+    // ever written (e.g. still in `init()`/setup): `cancel` must return
+    // immediately without waiting out `WATCHDOG_STOP_GRACE` or killing a
+    // perfectly healthy engine that was never told to search.
+    #[tokio::test]
+    async fn cancel_before_go_is_immediate_and_keeps_the_process() {
+        let exe = write_fake_engine();
+
+        let mut child = new_process_group(
+            Command::new(&exe)
+                .stdout(Stdio::piped())
+                .stdin(Stdio::piped())
+                .kill_on_drop(true),
+        )
+        .spawn()
+        .expect("spawn fake engine");
+        let pid = child.id().expect("pid");
+        let mut stdout = Stdout::new(child.stdout.take().expect("stdout"));
+        let mut stdin = BufWriter::new(child.stdin.take().expect("stdin"));
+
+        let (_tx, rx) = mpsc::channel(1);
+        let mut actor = StockfishActor {
+            rx,
+            exe,
+            init: StockfishInit {
+                nnue: String::new(),
+            },
+            initialized: true,
+            go_written: false,
+            logger: Logger::new(),
+        };
+
+        tokio::time::timeout(
+            WATCHDOG_STOP_GRACE,
+            actor.cancel(&mut stdin, &mut stdout, pid),
+        )
+        .await
+        .expect("cancel before go must not wait out the stop grace period")
+        .expect("cancel before go must not kill the process");
+
+        // The process was never touched: it still answers normally.
+        stdin.write_all(b"isready\n").await.unwrap();
+        stdin.flush().await.unwrap();
+        let line = stdout.read_line().await.unwrap();
+        assert_eq!(line.trim_end(), "readyok");
+
+        let _ = std::fs::remove_file(&actor.exe);
+    }
 }